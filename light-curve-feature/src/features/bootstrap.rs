@@ -0,0 +1,270 @@
+use crate::evaluator::*;
+use crate::rng::PCG32_STREAM;
+
+use rand::Rng;
+use rand_pcg::Pcg32;
+
+/// Resampling strategy used by [`BootstrapFeature`]
+#[derive(Clone, Copy, Debug, Serialize)]
+pub enum ResamplingMode {
+    /// Ordinary bootstrap: draw `N` indices with replacement (`N` being the series length),
+    /// `n_resamples` times
+    Bootstrap,
+    /// Delete-one jackknife: `N` resamples, each one dropping a single distinct observation
+    Jackknife,
+}
+
+/// Bootstrap / jackknife uncertainty wrapper for any [`FeatureEvaluator`]
+///
+/// Runs the wrapped feature `n_resamples` times (bootstrap) or once per observation (jackknife) on
+/// resampled copies of the `TimeSeries` and reports the mean and standard deviation of each
+/// underlying feature, which gives an empirical error bar to place next to any analytic one,
+/// e.g. [`LinearTrend`](crate::LinearTrend)'s slope next to its `slope_sigma2`.
+///
+/// Resampling uses a seedable PCG32 generator ([`rand_pcg::Pcg32`]) so that evaluations with the
+/// same `seed` are reproducible. Each resample draws indices (with replacement for bootstrap, by
+/// omission for jackknife), rebuilds a `TimeSeries` sorted by time (most evaluators require
+/// `sorting_required: true`) and evaluates the inner feature on it. A resample that drops below
+/// the inner feature's `min_ts_length` yields an `EvaluatorError`, which is skipped rather than
+/// propagated; if every resample fails the first encountered error is returned.
+///
+/// - Depends on: same as the inner feature
+/// - Minimum number of observations: same as the inner feature, and at least two for the
+///   jackknife mode (a single-point delete-one resample is never defined)
+/// - Number of features: twice the inner feature's number of features
+#[derive(Clone, Debug, Serialize)]
+pub struct BootstrapFeature<T, F>
+where
+    T: Float,
+    F: FeatureEvaluator<T>,
+{
+    feature: F,
+    mode: ResamplingMode,
+    n_resamples: usize,
+    seed: u64,
+    info: EvaluatorInfo,
+    names: Vec<String>,
+    descriptions: Vec<String>,
+    #[serde(skip)]
+    phantom: std::marker::PhantomData<T>,
+}
+
+impl<T, F> BootstrapFeature<T, F>
+where
+    T: Float,
+    F: FeatureEvaluator<T>,
+{
+    pub fn new(feature: F, mode: ResamplingMode, n_resamples: usize, seed: u64) -> Self {
+        let inner_info = feature.get_info();
+        let info = EvaluatorInfo {
+            size: 2 * inner_info.size,
+            min_ts_length: match mode {
+                ResamplingMode::Bootstrap => inner_info.min_ts_length,
+                ResamplingMode::Jackknife => usize::max(inner_info.min_ts_length, 2),
+            },
+            t_required: inner_info.t_required,
+            m_required: inner_info.m_required,
+            w_required: inner_info.w_required,
+            sorting_required: true,
+        };
+        let names = feature
+            .get_names()
+            .into_iter()
+            .flat_map(|name| vec![name.to_owned(), format!("{}_bootstrap_sigma", name)])
+            .collect();
+        let descriptions = feature
+            .get_descriptions()
+            .into_iter()
+            .flat_map(|descr| {
+                vec![
+                    descr.to_owned(),
+                    format!("bootstrap/jackknife standard deviation of {}", descr),
+                ]
+            })
+            .collect();
+        Self {
+            feature,
+            mode,
+            n_resamples,
+            seed,
+            info,
+            names,
+            descriptions,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Construct the ordinary bootstrap wrapper with `n_resamples` resamples
+    pub fn bootstrap(feature: F, n_resamples: usize, seed: u64) -> Self {
+        Self::new(feature, ResamplingMode::Bootstrap, n_resamples, seed)
+    }
+
+    /// Construct the delete-one jackknife wrapper, which always resamples once per observation
+    pub fn jackknife(feature: F, seed: u64) -> Self {
+        Self::new(feature, ResamplingMode::Jackknife, 0, seed)
+    }
+
+    fn resampled_ts(&self, ts: &mut TimeSeries<T>, indices: &[usize]) -> TimeSeries<T> {
+        let t: Vec<_> = indices.iter().map(|&i| ts.t.sample[i]).collect();
+        let m: Vec<_> = indices.iter().map(|&i| ts.m.sample[i]).collect();
+        match &ts.w {
+            Some(w) => {
+                let w: Vec<_> = indices.iter().map(|&i| w.sample[i]).collect();
+                let mut resampled = TimeSeries::new(t, m, w);
+                resampled.sort_by_time();
+                resampled
+            }
+            None => {
+                let mut resampled = TimeSeries::new_without_weight(t, m);
+                resampled.sort_by_time();
+                resampled
+            }
+        }
+    }
+
+    fn eval_resamples(&self, ts: &mut TimeSeries<T>) -> Result<Vec<Vec<T>>, EvaluatorError> {
+        let size = ts.lenu() as usize;
+        let mut rng = Pcg32::new(self.seed, PCG32_STREAM);
+        let mut results = Vec::new();
+        let mut first_error = None;
+
+        let mut run = |indices: Vec<usize>, results: &mut Vec<Vec<T>>| {
+            let mut resampled = self.resampled_ts(ts, &indices);
+            match self.feature.eval(&mut resampled) {
+                Ok(values) => results.push(values),
+                Err(err) => {
+                    if first_error.is_none() {
+                        first_error = Some(err);
+                    }
+                }
+            }
+        };
+
+        match self.mode {
+            ResamplingMode::Bootstrap => {
+                for _ in 0..self.n_resamples {
+                    let indices: Vec<_> = (0..size).map(|_| rng.gen_range(0..size)).collect();
+                    run(indices, &mut results);
+                }
+            }
+            ResamplingMode::Jackknife => {
+                for skip in 0..size {
+                    let indices: Vec<_> = (0..size).filter(|&i| i != skip).collect();
+                    run(indices, &mut results);
+                }
+            }
+        }
+
+        if results.is_empty() {
+            return Err(first_error.unwrap_or(EvaluatorError::FlatTimeSeries));
+        }
+        Ok(results)
+    }
+}
+
+impl<T, F> FeatureEvaluator<T> for BootstrapFeature<T, F>
+where
+    T: Float,
+    F: FeatureEvaluator<T>,
+{
+    fn eval(&self, ts: &mut TimeSeries<T>) -> Result<Vec<T>, EvaluatorError> {
+        self.check_ts_length(ts)?;
+        let samples = self.eval_resamples(ts)?;
+        let n = T::from(samples.len()).unwrap();
+        let size = self.feature.get_info().size;
+
+        let mut mean = vec![T::zero(); size];
+        for values in samples.iter() {
+            for (acc, &v) in mean.iter_mut().zip(values.iter()) {
+                *acc += v;
+            }
+        }
+        for m in mean.iter_mut() {
+            *m /= n;
+        }
+
+        let mut sigma = vec![T::zero(); size];
+        for values in samples.iter() {
+            for ((acc, &v), &m) in sigma.iter_mut().zip(values.iter()).zip(mean.iter()) {
+                *acc += (v - m) * (v - m);
+            }
+        }
+        // the delete-one jackknife variance estimator needs the (n-1) bias correction,
+        // Var_jack = (n-1)/n * sum((theta_i - theta_bar)^2), unlike the plain bootstrap sigma
+        let variance_factor = match self.mode {
+            ResamplingMode::Bootstrap => T::one() / n,
+            ResamplingMode::Jackknife => (n - T::one()) / n,
+        };
+        for s in sigma.iter_mut() {
+            *s = T::sqrt(*s * variance_factor);
+        }
+
+        let mut result = Vec::with_capacity(2 * size);
+        for (m, s) in mean.into_iter().zip(sigma.into_iter()) {
+            result.push(m);
+            result.push(s);
+        }
+        Ok(result)
+    }
+
+    fn get_info(&self) -> &EvaluatorInfo {
+        &self.info
+    }
+
+    fn get_names(&self) -> Vec<&str> {
+        self.names.iter().map(String::as_str).collect()
+    }
+
+    fn get_descriptions(&self) -> Vec<&str> {
+        self.descriptions.iter().map(String::as_str).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::LinearTrend;
+
+    #[test]
+    fn bootstrap_doubles_inner_size_and_names() {
+        let eval = BootstrapFeature::<f32, _>::bootstrap(LinearTrend::new(), 32, 0);
+        assert_eq!(eval.get_info().size, 4);
+        assert_eq!(
+            eval.get_names(),
+            vec![
+                "linear_trend",
+                "linear_trend_bootstrap_sigma",
+                "linear_trend_sigma",
+                "linear_trend_sigma_bootstrap_sigma",
+            ]
+        );
+    }
+
+    #[test]
+    fn bootstrap_is_reproducible_for_the_same_seed() {
+        let x = [0.0_f32, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+        let y = [0.1_f32, 1.2, 1.9, 3.3, 3.8, 5.1, 6.2, 6.9];
+
+        let eval_a = BootstrapFeature::bootstrap(LinearTrend::new(), 64, 123);
+        let eval_b = BootstrapFeature::bootstrap(LinearTrend::new(), 64, 123);
+
+        let mut ts_a = TimeSeries::new_without_weight(&x, &y);
+        let mut ts_b = TimeSeries::new_without_weight(&x, &y);
+
+        assert_eq!(
+            eval_a.eval(&mut ts_a).unwrap(),
+            eval_b.eval(&mut ts_b).unwrap()
+        );
+    }
+
+    #[test]
+    fn jackknife_runs_once_per_observation() {
+        let x = [0.0_f32, 1.0, 2.0, 3.0, 4.0, 5.0];
+        let y = [0.1_f32, 1.2, 1.9, 3.3, 3.8, 5.1];
+        let eval = BootstrapFeature::jackknife(LinearTrend::new(), 0);
+        let mut ts = TimeSeries::new_without_weight(&x, &y);
+        let result = eval.eval(&mut ts).unwrap();
+        assert_eq!(result.len(), 4);
+        assert!(result.iter().all(|x| x.is_finite()));
+    }
+}
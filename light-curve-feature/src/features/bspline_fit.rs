@@ -0,0 +1,110 @@
+use crate::evaluator::*;
+use crate::transformers::bspline::fit;
+
+/// Residual scatter and amplitude of a penalized cubic B-spline fit
+///
+/// Fits a penalized cubic B-spline (see [`crate::transformers::bspline`]) with uniformly spaced
+/// knots splitting the time span into `n_knots` spans and roughness penalty `lambda` to the light curve, by least
+/// squares weighted by the observation errors when available (`ts.w`). The two features are the standard
+/// deviation of the fit residuals `m - smoothed` (how much scatter the spline leaves unexplained)
+/// and the amplitude of the smoothed curve itself, `max(smoothed) - min(smoothed)` (the
+/// spline-implied amplitude of the underlying variability, with most of the high-frequency noise
+/// averaged out).
+///
+/// - Depends on: **time**, **magnitude**
+/// - Minimum number of observations: **4**
+/// - Number of features: **2**
+#[derive(Clone, Debug, Serialize)]
+pub struct BSplineFit<T> {
+    n_knots: usize,
+    lambda: T,
+    info: EvaluatorInfo,
+}
+
+impl<T> BSplineFit<T>
+where
+    T: Float,
+{
+    pub fn new(n_knots: usize, lambda: T) -> Self {
+        Self {
+            n_knots,
+            lambda,
+            info: EvaluatorInfo {
+                size: 2,
+                min_ts_length: 4,
+                t_required: true,
+                m_required: true,
+                w_required: false,
+                sorting_required: true,
+            },
+        }
+    }
+}
+
+impl<T> Default for BSplineFit<T>
+where
+    T: Float,
+{
+    fn default() -> Self {
+        Self::new(10, T::zero())
+    }
+}
+
+impl<T> FeatureEvaluator<T> for BSplineFit<T>
+where
+    T: Float,
+{
+    fn eval(&self, ts: &mut TimeSeries<T>) -> Result<Vec<T>, EvaluatorError> {
+        self.check_ts_length(ts)?;
+        let weights: Option<Vec<T>> = ts.w.as_ref().map(|w| w.sample.clone());
+        let result = fit(&ts.t.sample, &ts.m.sample, weights.as_deref(), self.n_knots, self.lambda)?;
+
+        let n = T::from(result.residuals.len()).unwrap();
+        let residual_mean = result.residuals.iter().fold(T::zero(), |a, &b| a + b) / n;
+        let residual_variance = result
+            .residuals
+            .iter()
+            .map(|&r| (r - residual_mean) * (r - residual_mean))
+            .fold(T::zero(), |a, b| a + b)
+            / n;
+        let residual_scatter = T::sqrt(residual_variance);
+
+        let min = result.smoothed.iter().copied().fold(T::infinity(), T::min);
+        let max = result.smoothed.iter().copied().fold(T::neg_infinity(), T::max);
+        let amplitude = max - min;
+
+        Ok(vec![residual_scatter, amplitude])
+    }
+
+    fn get_info(&self) -> &EvaluatorInfo {
+        &self.info
+    }
+
+    fn get_names(&self) -> Vec<&str> {
+        vec!["bspline_fit_residual_scatter", "bspline_fit_amplitude"]
+    }
+
+    fn get_descriptions(&self) -> Vec<&str> {
+        vec![
+            "standard deviation of the residuals of a penalized cubic B-spline fit",
+            "amplitude (max - min) of the penalized cubic B-spline fit",
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bspline_fit_on_a_sine_wave() {
+        let eval = BSplineFit::new(8, 0.0_f64);
+        let t: Vec<f64> = (0..100).map(|i| i as f64 * 0.1).collect();
+        let m: Vec<f64> = t.iter().map(|&x| x.sin()).collect();
+        let mut ts = TimeSeries::new_without_weight(&t, &m);
+        let result = eval.eval(&mut ts).unwrap();
+        assert_eq!(result.len(), 2);
+        assert!(result.iter().all(|x| x.is_finite()));
+        assert!(result[1] > 1.0 && result[1] < 2.1);
+    }
+}
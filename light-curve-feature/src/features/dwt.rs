@@ -0,0 +1,294 @@
+use crate::evaluator::*;
+
+/// Wavelet filter used by [`Dwt`]
+#[derive(Clone, Copy, Debug, Serialize)]
+pub enum DwtFilter {
+    /// Haar wavelet, the simplest orthogonal wavelet (two taps)
+    Haar,
+    /// Daubechies wavelet with four taps (`DB4`)
+    Daubechies4,
+}
+
+impl DwtFilter {
+    /// Low-pass (scaling) and high-pass (wavelet) analysis filter taps, normalised to unit norm
+    fn taps<T: Float>(self) -> (Vec<T>, Vec<T>) {
+        let from = |x: f64| T::from(x).unwrap();
+        match self {
+            Self::Haar => {
+                let c = from(std::f64::consts::FRAC_1_SQRT_2);
+                (vec![c, c], vec![c, -c])
+            }
+            // Daubechies-4 scaling coefficients, see I. Daubechies, "Ten Lectures on Wavelets" (1992)
+            Self::Daubechies4 => (
+                vec![
+                    from(0.482_962_913_144_534),
+                    from(0.836_516_303_737_808),
+                    from(0.224_143_868_042_013),
+                    from(-0.129_409_522_551_260),
+                ],
+                vec![
+                    from(-0.129_409_522_551_260),
+                    from(-0.224_143_868_042_013),
+                    from(0.836_516_303_737_808),
+                    from(-0.482_962_913_144_534),
+                ],
+            ),
+        }
+    }
+}
+
+/// Discrete wavelet transform energy features
+///
+/// The unevenly sampled light curve is first resampled onto a uniform, power-of-two length grid
+/// by linear interpolation over the observed time span (the same cadence-resampling caveat as
+/// [`EtaE`](crate::EtaE) applies: gaps are bridged by interpolation, so features are only
+/// meaningful when the light curve is reasonably densely and regularly sampled). A fast, in-place
+/// wavelet transform (Haar or Daubechies-4) is then applied to the resampled magnitudes: at each
+/// level the current approximation is convolved with the low-pass/high-pass filter pair and
+/// decimated by two, producing one array of detail coefficients per level and halving the
+/// approximation length. The features are the fraction of the total detail energy (sum of squared
+/// detail coefficients) carried by each level, from the coarsest to the finest, plus the variance
+/// of the finest-level detail coefficients, which captures short-timescale variability largely
+/// independent of the light curve's long-term trend.
+///
+/// - Depends on: **time**, **magnitude**
+/// - Minimum number of observations: **grid size** ($2^\mathrm{levels}$ at least)
+/// - Number of features: **levels + 1**
+#[derive(Clone, Debug, Serialize)]
+pub struct Dwt {
+    filter: DwtFilter,
+    levels: usize,
+    grid_size: usize,
+    info: EvaluatorInfo,
+}
+
+impl Dwt {
+    pub fn new(filter: DwtFilter, levels: usize, log2_grid_size: usize) -> Self {
+        assert!(levels >= 1, "Dwt should have at least one decomposition level");
+        assert!(
+            levels <= 16,
+            "Dwt supports at most 16 decomposition levels (levels = {})",
+            levels
+        );
+        assert!(
+            log2_grid_size >= levels,
+            "grid size 2^{} must fit at least `levels` = {} halvings",
+            log2_grid_size,
+            levels
+        );
+        let grid_size = 1_usize << log2_grid_size;
+        Self {
+            filter,
+            levels,
+            grid_size,
+            info: EvaluatorInfo {
+                size: levels + 1,
+                min_ts_length: grid_size,
+                t_required: true,
+                m_required: true,
+                w_required: false,
+                sorting_required: true,
+            },
+        }
+    }
+
+    pub fn haar(levels: usize, log2_grid_size: usize) -> Self {
+        Self::new(DwtFilter::Haar, levels, log2_grid_size)
+    }
+
+    pub fn daubechies4(levels: usize, log2_grid_size: usize) -> Self {
+        Self::new(DwtFilter::Daubechies4, levels, log2_grid_size)
+    }
+
+    /// Linearly resample `(t, m)` onto `self.grid_size` uniformly spaced points spanning `[t[0],
+    /// t[N-1]]`
+    fn resample<T: Float>(&self, t: &[T], m: &[T]) -> Vec<T> {
+        let t0 = t[0];
+        let t1 = t[t.len() - 1];
+        let span = t1 - t0;
+        let grid_size_m1 = T::from(self.grid_size - 1).unwrap();
+        let mut grid = vec![T::zero(); self.grid_size];
+        let mut j = 0_usize;
+        for (k, grid_value) in grid.iter_mut().enumerate() {
+            let tk = if span > T::zero() {
+                t0 + span * T::from(k).unwrap() / grid_size_m1
+            } else {
+                t0
+            };
+            while j + 2 < t.len() && t[j + 1] < tk {
+                j += 1;
+            }
+            let (tj, tj1) = (t[j], t[j + 1]);
+            let (mj, mj1) = (m[j], m[j + 1]);
+            *grid_value = if tj1 > tj {
+                mj + (mj1 - mj) * (tk - tj) / (tj1 - tj)
+            } else {
+                mj
+            };
+        }
+        grid
+    }
+
+    /// Run one level of the in-place fast wavelet transform, consuming `approx[..len]` and
+    /// writing the new, half-length approximation back into the first half of `approx` and the
+    /// detail coefficients into the returned vector
+    fn fwt_step<T: Float>(&self, approx: &mut [T], len: usize) -> Vec<T> {
+        let (low, high) = self.filter.taps::<T>();
+        let half = len / 2;
+        let mut new_approx = vec![T::zero(); half];
+        let mut detail = vec![T::zero(); half];
+        for i in 0..half {
+            let mut a = T::zero();
+            let mut d = T::zero();
+            for (k, (&lk, &hk)) in low.iter().zip(high.iter()).enumerate() {
+                let idx = (2 * i + k) % len;
+                a += lk * approx[idx];
+                d += hk * approx[idx];
+            }
+            new_approx[i] = a;
+            detail[i] = d;
+        }
+        approx[..half].copy_from_slice(&new_approx);
+        detail
+    }
+}
+
+impl<T> FeatureEvaluator<T> for Dwt
+where
+    T: Float,
+{
+    fn eval(&self, ts: &mut TimeSeries<T>) -> Result<Vec<T>, EvaluatorError> {
+        self.check_ts_length(ts)?;
+        let mut approx = self.resample(&ts.t.sample, &ts.m.sample);
+        // a constant offset carries no detail energy: removing the grid mean keeps its rounding
+        // noise out of the detail coefficients, so a flat light curve gives zero fractions
+        let grid_mean = approx.iter().fold(T::zero(), |a, &b| a + b) / T::from(self.grid_size).unwrap();
+        for x in approx.iter_mut() {
+            *x -= grid_mean;
+        }
+        let grid_energy = approx.iter().fold(T::zero(), |a, &b| a + b * b);
+        let mut len = self.grid_size;
+
+        let mut details = Vec::with_capacity(self.levels);
+        for _ in 0..self.levels {
+            let detail = self.fwt_step(&mut approx, len);
+            len /= 2;
+            details.push(detail);
+        }
+
+        let total_energy: T = details
+            .iter()
+            .map(|level| level.iter().map(|&x| x * x).fold(T::zero(), |a, b| a + b))
+            .fold(T::zero(), |a, b| a + b);
+
+        let mut result = Vec::with_capacity(self.levels + 1);
+        // `details` runs from the finest level (computed first) to the coarsest one
+        for level in details.iter().rev() {
+            let energy: T = level.iter().map(|&x| x * x).fold(T::zero(), |a, b| a + b);
+            let fraction = if total_energy > T::epsilon() * grid_energy {
+                energy / total_energy
+            } else {
+                T::zero()
+            };
+            result.push(fraction);
+        }
+
+        let finest = details.first().unwrap();
+        let n = T::from(finest.len()).unwrap();
+        let finest_mean = finest.iter().fold(T::zero(), |a, &b| a + b) / n;
+        let finest_variance = finest
+            .iter()
+            .map(|&x| (x - finest_mean) * (x - finest_mean))
+            .fold(T::zero(), |a, b| a + b)
+            / n;
+        result.push(finest_variance);
+
+        Ok(result)
+    }
+
+    fn get_info(&self) -> &EvaluatorInfo {
+        &self.info
+    }
+
+    fn get_names(&self) -> Vec<&str> {
+        // names are static for a fixed `levels`, longest practical `levels` is well under this list
+        const NAMES: [&str; 17] = [
+            "dwt_energy_level_1",
+            "dwt_energy_level_2",
+            "dwt_energy_level_3",
+            "dwt_energy_level_4",
+            "dwt_energy_level_5",
+            "dwt_energy_level_6",
+            "dwt_energy_level_7",
+            "dwt_energy_level_8",
+            "dwt_energy_level_9",
+            "dwt_energy_level_10",
+            "dwt_energy_level_11",
+            "dwt_energy_level_12",
+            "dwt_energy_level_13",
+            "dwt_energy_level_14",
+            "dwt_energy_level_15",
+            "dwt_energy_level_16",
+            "dwt_finest_detail_variance",
+        ];
+        let mut names: Vec<&str> = NAMES[..self.levels].to_vec();
+        names.push(NAMES[16]);
+        names
+    }
+
+    fn get_descriptions(&self) -> Vec<&str> {
+        const DESCRIPTIONS: [&str; 17] = [
+            "fraction of DWT detail energy at level 1 (coarsest)",
+            "fraction of DWT detail energy at level 2",
+            "fraction of DWT detail energy at level 3",
+            "fraction of DWT detail energy at level 4",
+            "fraction of DWT detail energy at level 5",
+            "fraction of DWT detail energy at level 6",
+            "fraction of DWT detail energy at level 7",
+            "fraction of DWT detail energy at level 8",
+            "fraction of DWT detail energy at level 9",
+            "fraction of DWT detail energy at level 10",
+            "fraction of DWT detail energy at level 11",
+            "fraction of DWT detail energy at level 12",
+            "fraction of DWT detail energy at level 13",
+            "fraction of DWT detail energy at level 14",
+            "fraction of DWT detail energy at level 15",
+            "fraction of DWT detail energy at level 16",
+            "variance of the finest-scale DWT detail coefficients",
+        ];
+        let mut descriptions: Vec<&str> = DESCRIPTIONS[..self.levels].to_vec();
+        descriptions.push(DESCRIPTIONS[16]);
+        descriptions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dwt_haar_sizes_and_finiteness() {
+        let eval = Dwt::haar(3, 5); // grid size 32
+        assert_eq!(eval.get_info().size, 4);
+        assert_eq!(eval.get_names().len(), 4);
+
+        let t: Vec<f32> = (0..64).map(|i| i as f32).collect();
+        let m: Vec<f32> = t.iter().map(|&x| (x * 0.3).sin()).collect();
+        let mut ts = TimeSeries::new_without_weight(&t, &m);
+        let result = eval.eval(&mut ts).unwrap();
+        assert_eq!(result.len(), 4);
+        assert!(result.iter().all(|x| x.is_finite()));
+        let energy_fractions_sum: f32 = result[..3].iter().sum();
+        assert!((energy_fractions_sum - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn dwt_daubechies4_on_a_flat_signal_has_zero_energy() {
+        let eval = Dwt::daubechies4(2, 4); // grid size 16
+        let t: Vec<f32> = (0..16).map(|i| i as f32).collect();
+        let m = vec![5.0_f32; 16];
+        let mut ts = TimeSeries::new_without_weight(&t, &m);
+        let result = eval.eval(&mut ts).unwrap();
+        assert!(result.iter().all(|&x| x.abs() < 1e-4));
+    }
+}
@@ -0,0 +1,273 @@
+use crate::evaluator::*;
+
+use num_traits::ToPrimitive;
+use std::collections::BTreeMap;
+
+/// One-sided (Hestenes) Jacobi SVD: iteratively rotates pairs of columns of the `m x n` matrix
+/// `a` until they are mutually orthogonal, at which point each column's norm is a singular value
+/// of the original matrix. `a` is consumed and used as scratch space.
+///
+/// Unlike eigendecomposing the `n x n` Gram matrix `A^T A`, this never forms `A^T A` and so never
+/// squares the matrix's condition number — the key numerical advantage of an SVD over a covariance
+/// eigendecomposition for near-degenerate (e.g. flat-folded) data.
+fn one_sided_jacobi_svd<T: Float>(mut a: Vec<Vec<T>>) -> Vec<T> {
+    let m = a.len();
+    let n = if m == 0 { 0 } else { a[0].len() };
+    let eps = T::from(1e-14).unwrap();
+    for _sweep in 0..100 {
+        let mut converged = true;
+        for p in 0..n {
+            for q in p + 1..n {
+                let mut alpha = T::zero();
+                let mut beta = T::zero();
+                let mut gamma = T::zero();
+                for row in a.iter() {
+                    alpha += row[p] * row[p];
+                    beta += row[q] * row[q];
+                    gamma += row[p] * row[q];
+                }
+                if T::abs(gamma) < eps * T::sqrt(alpha * beta) + eps {
+                    continue;
+                }
+                converged = false;
+                let zeta = (beta - alpha) / (T::from(2.0).unwrap() * gamma);
+                let t = T::signum(zeta) / (T::abs(zeta) + T::sqrt(T::one() + zeta * zeta));
+                let c = T::one() / T::sqrt(T::one() + t * t);
+                let s = c * t;
+                for row in a.iter_mut() {
+                    let rp = row[p];
+                    let rq = row[q];
+                    row[p] = c * rp - s * rq;
+                    row[q] = s * rp + c * rq;
+                }
+            }
+        }
+        if converged {
+            break;
+        }
+    }
+    let mut singular_values: Vec<T> = (0..n)
+        .map(|j| T::sqrt((0..m).fold(T::zero(), |acc, k| acc + a[k][j] * a[k][j])))
+        .collect();
+    singular_values.sort_by(|x, y| y.partial_cmp(x).unwrap());
+    singular_values
+}
+
+/// PCA/SVD shape features of a phase-folded light curve
+///
+/// Given a `period` (supplied directly, e.g. from the peak of a periodogram feature), each
+/// observation is assigned to a cycle number $\lfloor (t - t_0) / \mathrm{period} \rfloor$ and a
+/// phase bin; averaging magnitudes within each (cycle, bin) pair gives one row per cycle of a
+/// cycle-by-phase-bin matrix. Bins with no observations in a given cycle are mean-imputed from
+/// the other cycles' values in that bin. After centering each column, a numerically stable thin
+/// SVD of this matrix is computed directly, via one-sided Jacobi column rotation, rather than by
+/// eigendecomposing the `n_bins x n_bins` covariance matrix (which would square the matrix's
+/// condition number and lose precision on near-degenerate, e.g. flat-folded, light curves). The
+/// features are the fraction of variance explained by the first and by the first two singular
+/// components, and the ratio of the top two singular values, which quantifies how "single-humped"
+/// (large ratio) versus "double-humped" (ratio close to one) the folded light curve is.
+///
+/// At least two cycles and `min_filled_bins` distinct phase bins with real (non-imputed)
+/// observations are required, otherwise an [`EvaluatorError`] is returned.
+///
+/// - Depends on: **time**, **magnitude**
+/// - Minimum number of observations: **`n_bins`**
+/// - Number of features: **3**
+#[derive(Clone, Debug, Serialize)]
+pub struct PcaFold<T> {
+    period: T,
+    n_bins: usize,
+    min_filled_bins: usize,
+    info: EvaluatorInfo,
+}
+
+impl<T> PcaFold<T>
+where
+    T: Float,
+{
+    pub fn new(period: T, n_bins: usize, min_filled_bins: usize) -> Self {
+        assert!(n_bins >= 4, "PcaFold needs at least four phase bins");
+        assert!(
+            min_filled_bins <= n_bins,
+            "min_filled_bins cannot exceed the number of phase bins"
+        );
+        Self {
+            period,
+            n_bins,
+            min_filled_bins,
+            info: EvaluatorInfo {
+                size: 3,
+                min_ts_length: n_bins,
+                t_required: true,
+                m_required: true,
+                w_required: false,
+                sorting_required: true,
+            },
+        }
+    }
+
+    fn phase_bin(&self, dt: T) -> (i64, usize) {
+        let cycles_elapsed = T::floor(dt);
+        let phase = dt - cycles_elapsed;
+        let cycle = cycles_elapsed.to_i64().unwrap_or(0);
+        let bin = usize::min(
+            (phase * T::from(self.n_bins).unwrap()).to_usize().unwrap_or(0),
+            self.n_bins - 1,
+        );
+        (cycle, bin)
+    }
+}
+
+impl<T> FeatureEvaluator<T> for PcaFold<T>
+where
+    T: Float,
+{
+    fn eval(&self, ts: &mut TimeSeries<T>) -> Result<Vec<T>, EvaluatorError> {
+        let size = self.check_ts_length(ts)?;
+        let t0 = ts.t.sample[0];
+        let n_bins = self.n_bins;
+
+        let mut cycles: BTreeMap<i64, (Vec<T>, Vec<usize>)> = BTreeMap::new();
+        for i in 0..size {
+            let dt = (ts.t.sample[i] - t0) / self.period;
+            let (cycle, bin) = self.phase_bin(dt);
+            let row = cycles
+                .entry(cycle)
+                .or_insert_with(|| (vec![T::zero(); n_bins], vec![0_usize; n_bins]));
+            row.0[bin] += ts.m.sample[i];
+            row.1[bin] += 1;
+        }
+
+        if cycles.len() < 2 {
+            return Err(EvaluatorError::ShortTimeSeries {
+                actual: cycles.len(),
+                minimum: 2,
+            });
+        }
+
+        // average magnitudes within (cycle, bin), tracking which bins ever had real data
+        let mut rows: Vec<Vec<Option<T>>> = Vec::with_capacity(cycles.len());
+        let mut filled_anywhere = vec![false; n_bins];
+        for (sums, counts) in cycles.values() {
+            let row: Vec<Option<T>> = sums
+                .iter()
+                .zip(counts.iter())
+                .enumerate()
+                .map(|(bin, (&sum, &count))| {
+                    if count > 0 {
+                        filled_anywhere[bin] = true;
+                        Some(sum / T::from(count).unwrap())
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            rows.push(row);
+        }
+
+        let filled_bins_overall = filled_anywhere.iter().filter(|&&f| f).count();
+        if filled_bins_overall < self.min_filled_bins {
+            return Err(EvaluatorError::ShortTimeSeries {
+                actual: filled_bins_overall,
+                minimum: self.min_filled_bins,
+            });
+        }
+
+        // mean-impute missing bins from the other cycles' value in that bin
+        let n_rows = T::from(rows.len()).unwrap();
+        let mut column_mean = vec![T::zero(); n_bins];
+        for bin in 0..n_bins {
+            let (sum, count) = rows.iter().fold((T::zero(), 0_usize), |(sum, count), row| match row[bin] {
+                Some(v) => (sum + v, count + 1),
+                None => (sum, count),
+            });
+            column_mean[bin] = if count > 0 { sum / T::from(count).unwrap() } else { T::zero() };
+        }
+        let mut matrix: Vec<Vec<T>> = rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .enumerate()
+                    .map(|(bin, &value)| value.unwrap_or(column_mean[bin]))
+                    .collect()
+            })
+            .collect();
+
+        // centre each column on its (post-imputation) mean
+        for bin in 0..n_bins {
+            let mean = matrix.iter().fold(T::zero(), |acc, row| acc + row[bin]) / n_rows;
+            for row in matrix.iter_mut() {
+                row[bin] -= mean;
+            }
+        }
+
+        let singular_values = one_sided_jacobi_svd(matrix);
+        let eigenvalues: Vec<T> = singular_values.iter().map(|&sv| sv * sv).collect();
+        let total: T = eigenvalues.iter().fold(T::zero(), |a, &b| a + b);
+
+        let variance_fraction_1 = if total > T::zero() { eigenvalues[0] / total } else { T::zero() };
+        let variance_fraction_2 = if total > T::zero() {
+            (eigenvalues[0] + eigenvalues[1]) / total
+        } else {
+            T::zero()
+        };
+        let eps = T::from(1e-30).unwrap();
+        // eigenvalues are squared singular values and so are never negative, but guard explicitly
+        // (consistent with every other branch above) rather than relying on that invariant holding
+        let top = T::max(eigenvalues[0], T::zero());
+        let singular_value_ratio = T::sqrt(top / T::max(eigenvalues[1], eps));
+
+        Ok(vec![variance_fraction_1, variance_fraction_2, singular_value_ratio])
+    }
+
+    fn get_info(&self) -> &EvaluatorInfo {
+        &self.info
+    }
+
+    fn get_names(&self) -> Vec<&str> {
+        vec![
+            "pca_fold_variance_fraction_1",
+            "pca_fold_variance_fraction_2",
+            "pca_fold_singular_value_ratio",
+        ]
+    }
+
+    fn get_descriptions(&self) -> Vec<&str> {
+        vec![
+            "fraction of phase-folded shape variance explained by the first principal component",
+            "fraction of phase-folded shape variance explained by the first two principal components",
+            "ratio of the top two singular values of the phase-folded, binned light curve",
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pca_fold_single_humped_sine_has_a_large_singular_value_ratio() {
+        let period = 10.0_f64;
+        let eval = PcaFold::new(period, 16, 4);
+        let t: Vec<f64> = (0..400).map(|i| i as f64 * 0.25).collect();
+        let m: Vec<f64> = t
+            .iter()
+            .map(|&ti| (2.0 * std::f64::consts::PI * ti / period).sin())
+            .collect();
+        let mut ts = TimeSeries::new_without_weight(&t, &m);
+        let result = eval.eval(&mut ts).unwrap();
+        assert_eq!(result.len(), 3);
+        assert!(result.iter().all(|x| x.is_finite()));
+        assert!(result[0] > 0.5);
+        assert!(result[2] > 1.0);
+    }
+
+    #[test]
+    fn pca_fold_requires_at_least_two_cycles() {
+        let eval = PcaFold::new(1000.0_f64, 8, 2);
+        let t: Vec<f64> = (0..20).map(|i| i as f64).collect();
+        let m: Vec<f64> = t.iter().map(|&x| x.sin()).collect();
+        let mut ts = TimeSeries::new_without_weight(&t, &m);
+        assert!(eval.eval(&mut ts).is_err());
+    }
+}
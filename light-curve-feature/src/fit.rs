@@ -0,0 +1,264 @@
+use crate::evaluator::*;
+
+/// Convergence status reported by [`curve_fit`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum FitStatus {
+    /// The weighted $\chi^2$ stopped improving within `tol`
+    Converged,
+    /// `max_iterations` was reached before convergence
+    MaxIterationsReached,
+}
+
+/// Result of a [`curve_fit`] call
+pub struct CurveFitResult<T> {
+    pub params: Vec<T>,
+    pub status: FitStatus,
+    pub reduced_chi2: T,
+    /// Parameter covariance matrix, $(J^T W J)^{-1}$ at the final iterate
+    pub covariance: Vec<Vec<T>>,
+}
+
+/// Solve the symmetric positive-definite system `a * x = b` via Cholesky decomposition, returning
+/// `None` if `a` is not positive-definite (within floating point tolerance)
+fn cholesky_solve<T: Float>(a: &[Vec<T>], b: &[T]) -> Option<Vec<T>> {
+    let n = b.len();
+    let mut l = vec![vec![T::zero(); n]; n];
+    for i in 0..n {
+        for j in 0..=i {
+            let mut sum = a[i][j];
+            for (li, lj) in l[i][..j].iter().zip(l[j][..j].iter()) {
+                sum -= *li * *lj;
+            }
+            if i == j {
+                if sum <= T::zero() {
+                    return None;
+                }
+                l[i][j] = T::sqrt(sum);
+            } else {
+                l[i][j] = sum / l[j][j];
+            }
+        }
+    }
+    let mut y = vec![T::zero(); n];
+    for i in 0..n {
+        let mut sum = b[i];
+        for (k, yk) in y[..i].iter().enumerate() {
+            sum -= l[i][k] * *yk;
+        }
+        y[i] = sum / l[i][i];
+    }
+    let mut x = vec![T::zero(); n];
+    for i in (0..n).rev() {
+        let mut sum = y[i];
+        for k in i + 1..n {
+            sum -= l[k][i] * x[k];
+        }
+        x[i] = sum / l[i][i];
+    }
+    Some(x)
+}
+
+/// Inverse of a symmetric positive-definite matrix, column by column via [`cholesky_solve`]
+fn cholesky_inverse<T: Float>(a: &[Vec<T>]) -> Option<Vec<Vec<T>>> {
+    let n = a.len();
+    let mut inverse = vec![vec![T::zero(); n]; n];
+    for col in 0..n {
+        let mut e = vec![T::zero(); n];
+        e[col] = T::one();
+        let x = cholesky_solve(a, &e)?;
+        for row in 0..n {
+            inverse[row][col] = x[row];
+        }
+    }
+    Some(inverse)
+}
+
+/// Levenberg-Marquardt nonlinear least-squares solver
+///
+/// Fits a user-supplied parametric model by minimising the weighted sum of squared residuals
+/// `r(params)`, given `residual` and `jacobian` closures (the latter returning the `n_points x
+/// n_params` matrix $\partial r_i / \partial \mathrm{params}_j$). At each iteration the damped
+/// normal equations
+/// $$
+/// (J^T W J + \mu\\,\mathrm{diag}(J^T W J))\\,\delta = -J^T W r
+/// $$
+/// are solved by Cholesky decomposition; if the step decreases the weighted $\chi^2$ it is
+/// accepted and $\mu$ is shrunk, otherwise $\mu$ is grown and the step is retried. Iteration is
+/// bounded by `max_iterations`, and a normal matrix that is not positive-definite (a degenerate
+/// or overparametrised model) is reported as [`EvaluatorError::FitterError`] rather than panicking.
+/// Requires `n_points` $\geq$ `params0.len()`, i.e. at least as many observations as free
+/// parameters, otherwise the same error is returned.
+#[allow(clippy::too_many_arguments)]
+pub fn curve_fit<T, Residual, Jacobian>(
+    params0: &[T],
+    weights: Option<&[T]>,
+    n_points: usize,
+    max_iterations: usize,
+    tol: T,
+    residual: Residual,
+    jacobian: Jacobian,
+) -> Result<CurveFitResult<T>, EvaluatorError>
+where
+    T: Float,
+    Residual: Fn(&[T]) -> Vec<T>,
+    Jacobian: Fn(&[T]) -> Vec<Vec<T>>,
+{
+    let n_params = params0.len();
+    if n_points < n_params {
+        return Err(EvaluatorError::FitterError(format!(
+            "curve_fit requires at least as many points ({}) as free parameters ({})",
+            n_points, n_params
+        )));
+    }
+
+    let weight = |i: usize| weights.map_or(T::one(), |w| w[i]);
+
+    let weighted_chi2 = |r: &[T]| -> T {
+        r.iter()
+            .enumerate()
+            .fold(T::zero(), |acc, (i, &ri)| acc + weight(i) * ri * ri)
+    };
+
+    let mut params = params0.to_vec();
+    let mut r = residual(&params);
+    let mut chi2 = weighted_chi2(&r);
+    let mut mu = T::from(1e-3).unwrap();
+    let mut status = FitStatus::MaxIterationsReached;
+    let mut last_normal = vec![vec![T::zero(); n_params]; n_params];
+
+    'outer: for _ in 0..max_iterations {
+        let j = jacobian(&params);
+
+        let mut normal = vec![vec![T::zero(); n_params]; n_params];
+        let mut rhs = vec![T::zero(); n_params];
+        for i in 0..n_points {
+            let w = weight(i);
+            for a in 0..n_params {
+                rhs[a] -= w * j[i][a] * r[i];
+                for b in 0..n_params {
+                    normal[a][b] += w * j[i][a] * j[i][b];
+                }
+            }
+        }
+        last_normal = normal.clone();
+
+        // inner loop: grow mu until a damped step actually reduces chi2
+        let mut accepted = false;
+        for _ in 0..32 {
+            let mut damped = normal.clone();
+            for (i, row) in damped.iter_mut().enumerate() {
+                row[i] += mu * normal[i][i];
+            }
+            let delta = match cholesky_solve(&damped, &rhs) {
+                Some(delta) => delta,
+                None => {
+                    mu *= T::from(10.0).unwrap();
+                    continue;
+                }
+            };
+            let new_params: Vec<T> = params.iter().zip(delta.iter()).map(|(&p, &d)| p + d).collect();
+            let new_r = residual(&new_params);
+            let new_chi2 = weighted_chi2(&new_r);
+            if new_chi2 < chi2 {
+                let improvement = chi2 - new_chi2;
+                params = new_params;
+                r = new_r;
+                mu /= T::from(10.0).unwrap();
+                accepted = true;
+                if improvement < tol * chi2 {
+                    chi2 = new_chi2;
+                    status = FitStatus::Converged;
+                    break 'outer;
+                }
+                chi2 = new_chi2;
+                break;
+            } else {
+                mu *= T::from(10.0).unwrap();
+            }
+        }
+        if !accepted {
+            status = FitStatus::Converged;
+            break;
+        }
+    }
+
+    let reduced_chi2 = chi2 / T::from(usize::max(n_points - n_params, 1)).unwrap();
+    // J^T W J at the final parameters, for the covariance matrix
+    let j = jacobian(&params);
+    let mut final_normal = vec![vec![T::zero(); n_params]; n_params];
+    for i in 0..n_points {
+        let w = weight(i);
+        for a in 0..n_params {
+            for b in 0..n_params {
+                final_normal[a][b] += w * j[i][a] * j[i][b];
+            }
+        }
+    }
+    let covariance = cholesky_inverse(&final_normal).ok_or_else(|| {
+        EvaluatorError::FitterError("curve_fit: singular normal matrix at the final iterate".to_owned())
+    })?;
+
+    Ok(CurveFitResult {
+        params,
+        status,
+        reduced_chi2,
+        covariance,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curve_fit_recovers_parameters_of_a_noiseless_quadratic() {
+        let t: Vec<f64> = (0..20).map(|i| i as f64).collect();
+        let (a, b, c) = (2.0_f64, -3.0, 1.0);
+        let y: Vec<f64> = t.iter().map(|&ti| a * ti * ti + b * ti + c).collect();
+
+        let residual = |params: &[f64]| -> Vec<f64> {
+            t.iter()
+                .zip(y.iter())
+                .map(|(&ti, &yi)| params[0] * ti * ti + params[1] * ti + params[2] - yi)
+                .collect()
+        };
+        let jacobian = |_: &[f64]| -> Vec<Vec<f64>> { t.iter().map(|&ti| vec![ti * ti, ti, 1.0]).collect() };
+
+        let result = curve_fit(&[1.0, 1.0, 1.0], None, t.len(), 50, 1e-10, residual, jacobian).unwrap();
+
+        assert_eq!(result.status, FitStatus::Converged);
+        assert!((result.params[0] - a).abs() < 1e-6);
+        assert!((result.params[1] - b).abs() < 1e-6);
+        assert!((result.params[2] - c).abs() < 1e-6);
+        assert!(result.reduced_chi2 < 1e-10);
+    }
+
+    #[test]
+    fn curve_fit_rejects_fewer_points_than_parameters() {
+        let residual = |params: &[f64]| -> Vec<f64> { vec![params[0]] };
+        let jacobian = |_: &[f64]| -> Vec<Vec<f64>> { vec![vec![1.0, 1.0]] };
+
+        let result = curve_fit(&[0.0, 0.0], None, 1, 10, 1e-8, residual, jacobian);
+        assert!(matches!(result, Err(EvaluatorError::FitterError(_))));
+    }
+
+    #[test]
+    fn curve_fit_reports_a_singular_final_normal_matrix() {
+        // a model with two perfectly degenerate parameters (only their sum is identifiable): the
+        // Jacobian's two columns are always identical, so J^T W J is singular at every iterate,
+        // including the last one used to build the covariance matrix
+        let t: Vec<f64> = (0..5).map(|i| i as f64 + 1.0).collect();
+        let y: Vec<f64> = t.iter().map(|&ti| 2.0 * ti).collect();
+
+        let residual = |params: &[f64]| -> Vec<f64> {
+            t.iter()
+                .zip(y.iter())
+                .map(|(&ti, &yi)| (params[0] + params[1]) * ti - yi)
+                .collect()
+        };
+        let jacobian = |_: &[f64]| -> Vec<Vec<f64>> { t.iter().map(|&ti| vec![ti, ti]).collect() };
+
+        let result = curve_fit(&[0.5, 0.5], None, t.len(), 50, 1e-8, residual, jacobian);
+        assert!(matches!(result, Err(EvaluatorError::FitterError(_))));
+    }
+}
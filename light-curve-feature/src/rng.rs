@@ -0,0 +1,6 @@
+/// Crate-wide odd increment ("stream") constant for every seedable `rand_pcg::Pcg32` used for
+/// resampling or subsampling inside a feature, e.g. [`crate::BootstrapFeature`] and
+/// [`crate::RobustLinearTrend`]. Sharing a single constant (rather than letting each feature pick
+/// its own) is what makes `Pcg32::new(seed, PCG32_STREAM)` bit-identical across features and
+/// platforms for a given `seed`.
+pub(crate) const PCG32_STREAM: u64 = 0x5851_f42d_4c95_7f2d;
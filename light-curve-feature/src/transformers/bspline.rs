@@ -0,0 +1,240 @@
+use crate::evaluator::*;
+
+/// Degree of the B-spline basis used throughout this module (cubic)
+const DEGREE: usize = 3;
+
+/// What a [`transform`] call should replace the light curve's magnitudes with
+#[derive(Clone, Copy, Debug, Serialize)]
+pub enum BSplineTarget {
+    /// Replace `m` with the smoothed (fitted) curve
+    Smoothed,
+    /// Replace `m` with the fit residuals `m - smoothed`
+    Residual,
+}
+
+/// Result of fitting a penalized cubic B-spline to `(t, m)`
+pub struct BSplineFitResult<T> {
+    pub coefficients: Vec<T>,
+    pub smoothed: Vec<T>,
+    pub residuals: Vec<T>,
+}
+
+/// Build a clamped, open uniform knot vector splitting `[t_min, t_max]` into `n_knots` equal spans
+/// (`n_knots - 1` interior knots).
+///
+/// The returned vector has `n_knots + 2 * DEGREE + 1` entries; together with `DEGREE` it defines
+/// `n_knots + DEGREE` cubic B-spline basis functions covering `[t_min, t_max]`.
+fn knot_vector<T: Float>(n_knots: usize, t_min: T, t_max: T) -> Vec<T> {
+    let mut knots = Vec::with_capacity(n_knots + 2 * DEGREE);
+    for _ in 0..=DEGREE {
+        knots.push(t_min);
+    }
+    for i in 1..n_knots {
+        let frac = T::from(i).unwrap() / T::from(n_knots).unwrap();
+        knots.push(t_min + (t_max - t_min) * frac);
+    }
+    for _ in 0..=DEGREE {
+        knots.push(t_max);
+    }
+    knots
+}
+
+/// Cox-de Boor recursion for the value of the `i`-th basis function of the given `degree` at `t`
+fn basis_value<T: Float>(i: usize, degree: usize, knots: &[T], t: T) -> T {
+    if degree == 0 {
+        let last_span = i + 1 == knots.len() - DEGREE - 1;
+        if (knots[i] <= t && t < knots[i + 1]) || (last_span && t == knots[knots.len() - 1]) {
+            T::one()
+        } else {
+            T::zero()
+        }
+    } else {
+        let mut value = T::zero();
+        let left_denom = knots[i + degree] - knots[i];
+        if left_denom > T::zero() {
+            value += (t - knots[i]) / left_denom * basis_value(i, degree - 1, knots, t);
+        }
+        let right_denom = knots[i + degree + 1] - knots[i + 1];
+        if right_denom > T::zero() {
+            value += (knots[i + degree + 1] - t) / right_denom * basis_value(i + 1, degree - 1, knots, t);
+        }
+        value
+    }
+}
+
+/// Solve the symmetric positive-definite system `a * x = b` via Cholesky decomposition
+fn cholesky_solve<T: Float>(a: &[Vec<T>], b: &[T]) -> Vec<T> {
+    let n = b.len();
+    let mut l = vec![vec![T::zero(); n]; n];
+    for i in 0..n {
+        for j in 0..=i {
+            let mut sum = a[i][j];
+            for (li, lj) in l[i][..j].iter().zip(l[j][..j].iter()) {
+                sum -= *li * *lj;
+            }
+            l[i][j] = if i == j { T::sqrt(sum) } else { sum / l[j][j] };
+        }
+    }
+    let mut y = vec![T::zero(); n];
+    for i in 0..n {
+        let mut sum = b[i];
+        for (k, yk) in y[..i].iter().enumerate() {
+            sum -= l[i][k] * *yk;
+        }
+        y[i] = sum / l[i][i];
+    }
+    let mut x = vec![T::zero(); n];
+    for i in (0..n).rev() {
+        let mut sum = y[i];
+        for k in i + 1..n {
+            sum -= l[k][i] * x[k];
+        }
+        x[i] = sum / l[i][i];
+    }
+    x
+}
+
+/// Fit a penalized (P-spline) cubic B-spline to `(t, m)`, optionally weighted by `w = 1 / sigma^2`.
+///
+/// Knots split the observed time span into `n_knots` equal spans; since the basis has
+/// `n_knots + DEGREE` coefficients, `n_knots` is silently reduced so that the number of
+/// coefficients never exceeds `N` for `N >= DEGREE + 1` (shorter series always get `DEGREE + 1`
+/// coefficients), which keeps the least-squares problem from being under-determined for short
+/// light curves of at least `DEGREE + 1` points. A second-difference roughness
+/// penalty weighted by `lambda` is added to the normal equations, `(B^T W B + lambda D^T D) c =
+/// B^T W m`, and solved by Cholesky decomposition.
+pub fn fit<T: Float>(t: &[T], m: &[T], w: Option<&[T]>, n_knots: usize, lambda: T) -> Result<BSplineFitResult<T>, EvaluatorError> {
+    let size = t.len();
+    let max_knots = usize::max(1, size.saturating_sub(DEGREE));
+    let n_knots = usize::min(n_knots, max_knots);
+    let knots = knot_vector(n_knots, t[0], t[size - 1]);
+    let n_coeffs = knots.len() - DEGREE - 1;
+
+    // assemble the basis matrix B (size x n_coeffs)
+    let mut basis = vec![vec![T::zero(); n_coeffs]; size];
+    for (row, &ti) in basis.iter_mut().zip(t.iter()) {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = basis_value(j, DEGREE, &knots, ti);
+        }
+    }
+
+    // normal equations B^T W B
+    let mut normal = vec![vec![T::zero(); n_coeffs]; n_coeffs];
+    let mut rhs = vec![T::zero(); n_coeffs];
+    for (row_idx, row) in basis.iter().enumerate() {
+        let weight = w.map_or(T::one(), |w| w[row_idx]);
+        for (a, &ba) in row.iter().enumerate() {
+            rhs[a] += weight * ba * m[row_idx];
+            for (b, &bb) in row.iter().enumerate() {
+                normal[a][b] += weight * ba * bb;
+            }
+        }
+    }
+
+    // second-difference roughness penalty lambda * D^T D
+    if n_coeffs >= 3 {
+        for i in 0..n_coeffs - 2 {
+            let rows: [(usize, T); 3] = [(i, T::one()), (i + 1, -(T::one() + T::one())), (i + 2, T::one())];
+            for &(a, ca) in rows.iter() {
+                for &(b, cb) in rows.iter() {
+                    normal[a][b] += lambda * ca * cb;
+                }
+            }
+        }
+    }
+
+    let coefficients = cholesky_solve(&normal, &rhs);
+
+    let smoothed: Vec<T> = basis
+        .iter()
+        .map(|row| {
+            row.iter()
+                .zip(coefficients.iter())
+                .fold(T::zero(), |acc, (&b, &c)| acc + b * c)
+        })
+        .collect();
+    let residuals: Vec<T> = m.iter().zip(smoothed.iter()).map(|(&mi, &si)| mi - si).collect();
+
+    Ok(BSplineFitResult {
+        coefficients,
+        smoothed,
+        residuals,
+    })
+}
+
+/// Replace `ts.m` with the smoothed curve or its residuals, see [`BSplineTarget`]
+pub fn transform<T: Float>(
+    ts: &mut TimeSeries<T>,
+    n_knots: usize,
+    lambda: T,
+    target: BSplineTarget,
+) -> Result<(), EvaluatorError> {
+    let weights: Option<Vec<T>> = ts.w.as_ref().map(|w| w.sample.clone());
+    let result = fit(&ts.t.sample, &ts.m.sample, weights.as_deref(), n_knots, lambda)?;
+    let new_m = match target {
+        BSplineTarget::Smoothed => result.smoothed,
+        BSplineTarget::Residual => result.residuals,
+    };
+    ts.m = DataSample::new(new_m);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bspline_fits_a_straight_line_almost_exactly() {
+        let t: Vec<f64> = (0..30).map(|i| i as f64).collect();
+        let m: Vec<f64> = t.iter().map(|&x| 2.0 + 0.5 * x).collect();
+        let result = fit(&t, &m, None, 6, 0.0).unwrap();
+        for (&mi, &si) in m.iter().zip(result.smoothed.iter()) {
+            assert!((mi - si).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn bspline_knot_count_is_capped_for_short_series() {
+        let t: Vec<f64> = (0..6).map(|i| i as f64).collect();
+        let m: Vec<f64> = t.iter().map(|&x| x.sin()).collect();
+        // ask for far more knots than the series could ever support
+        let result = fit(&t, &m, None, 100, 0.0).unwrap();
+        assert_eq!(result.smoothed.len(), 6);
+        assert!(result.smoothed.iter().all(|x| x.is_finite()));
+    }
+
+    #[test]
+    fn bspline_weighted_fit_differs_from_unweighted_for_noisy_data() {
+        let t: Vec<f64> = (0..30).map(|i| i as f64).collect();
+        // every other point is a high-scatter outlier; down-weighting it should pull the fit
+        // towards the low-scatter points more than an unweighted fit does
+        let m: Vec<f64> = t
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| 2.0 + 0.5 * x + if i % 2 == 0 { 3.0 } else { -3.0 })
+            .collect();
+        let w: Vec<f64> = (0..30).map(|i| if i % 2 == 0 { 0.01 } else { 1.0 }).collect();
+
+        let unweighted = fit(&t, &m, None, 6, 0.0).unwrap();
+        let weighted = fit(&t, &m, Some(&w), 6, 0.0).unwrap();
+
+        assert!(weighted.smoothed.iter().all(|x| x.is_finite()));
+        let max_diff = unweighted
+            .smoothed
+            .iter()
+            .zip(weighted.smoothed.iter())
+            .fold(0.0_f64, |acc, (&u, &w)| f64::max(acc, (u - w).abs()));
+        assert!(max_diff > 0.1);
+    }
+
+    #[test]
+    fn bspline_knot_count_is_never_underdetermined_at_the_minimum_series_length() {
+        // size == 4 is BSplineFit's declared min_ts_length; n_coeffs must never exceed size
+        let t: Vec<f64> = (0..4).map(|i| i as f64).collect();
+        let m: Vec<f64> = t.iter().map(|&x| 1.0 + 0.5 * x).collect();
+        let result = fit(&t, &m, None, 100, 0.0).unwrap();
+        assert_eq!(result.smoothed.len(), 4);
+        assert!(result.smoothed.iter().all(|x| x.is_finite()));
+        assert!(result.coefficients.iter().all(|x| x.is_finite()));
+    }
+}